@@ -1,32 +1,138 @@
-use std::fmt::{Display, Formatter};
-use derive_more::From;
+use std::error::Error as _;
+use std::fmt::{Debug, Display, Formatter};
 
 pub type Result<T> = core::result::Result<T, Error>;
-#[derive(Debug, From)]
-pub enum Error {
-    #[from]
+
+/// The semantic category of an [`Error`], independent of whatever caused it.
+#[derive(Debug)]
+pub enum ErrorKind {
     Custom(String),
+    Io,
+    Fs(crate::fs::Error),
+}
 
-    #[from]
-    Io(std::io::Error)
+/// A crate-wide error that keeps the causal chain instead of flattening it
+/// into a string, so callers can downcast back to the original cause (see
+/// [`Error::io_error_source`]).
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    #[cfg(feature = "backtrace")]
+    backtrace: std::backtrace::Backtrace,
 }
 
 impl Error {
+    pub fn new(
+        kind: ErrorKind,
+        source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    ) -> Self {
+        Self {
+            kind,
+            source,
+            #[cfg(feature = "backtrace")]
+            backtrace: std::backtrace::Backtrace::capture(),
+        }
+    }
+
+    pub fn kind_only(kind: ErrorKind) -> Self {
+        Self::new(kind, None)
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
     pub fn custom(msg: impl Display) -> Self {
-        Self::Custom(msg.to_string())
+        Self::kind_only(ErrorKind::Custom(msg.to_string()))
+    }
+
+    /// Recovers the underlying OS error, if this error was caused by one,
+    /// without having to match on `kind()`'s shape.
+    pub fn io_error_source(&self) -> Option<&std::io::Error> {
+        self.source()
+            .and_then(|s| s.downcast_ref::<std::io::Error>())
+    }
+
+    /// The backtrace captured when this error was constructed, if the
+    /// `backtrace` feature is enabled and `RUST_BACKTRACE` was set at the
+    /// time.
+    #[cfg(feature = "backtrace")]
+    pub fn backtrace(&self) -> Option<&std::backtrace::Backtrace> {
+        (self.backtrace.status() == std::backtrace::BacktraceStatus::Captured)
+            .then_some(&self.backtrace)
+    }
+
+    /// Maps this error to a standard sysexits.h exit code, so the binary
+    /// can branch on exit status in shell pipelines.
+    pub fn exit_code(&self) -> i32 {
+        const EX_USAGE: i32 = 64;
+        const EX_DATAERR: i32 = 65;
+        const EX_NOINPUT: i32 = 66;
+        const EX_SOFTWARE: i32 = 70;
+        const EX_IOERR: i32 = 74;
+
+        match &self.kind {
+            ErrorKind::Custom(_) => EX_SOFTWARE,
+            ErrorKind::Io => EX_NOINPUT,
+            ErrorKind::Fs(fs_err) => match fs_err {
+                crate::fs::Error::PathNotFound(_) => EX_NOINPUT,
+                crate::fs::Error::NotADirectory(_) => EX_USAGE,
+                crate::fs::Error::EmptyDirectory(_) => EX_DATAERR,
+                crate::fs::Error::EntryUnreadable(_) => EX_IOERR,
+            },
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::new(ErrorKind::Io, Some(Box::new(value)))
+    }
+}
+
+impl From<String> for Error {
+    fn from(value: String) -> Self {
+        Self::kind_only(ErrorKind::Custom(value))
     }
 }
 
 impl From<&str> for Error {
     fn from(value: &str) -> Self {
-        Self::Custom(value.to_string())
+        Self::kind_only(ErrorKind::Custom(value.to_string()))
+    }
+}
+
+impl From<crate::fs::Error> for Error {
+    fn from(value: crate::fs::Error) -> Self {
+        Self::kind_only(ErrorKind::Fs(value))
     }
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{self:?}")
+        write!(f, "{:?}", self.kind)?;
+        if let Some(source) = self.source() {
+            write!(f, ": {source}")?;
+        }
+
+        #[cfg(feature = "backtrace")]
+        if let Some(backtrace) = self.backtrace() {
+            write!(f, "\n\nbacktrace:\n{backtrace}")?;
+        }
+
+        Ok(())
     }
 }
 
-impl std::error::Error for Error {}
\ No newline at end of file
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|s| s as &(dyn std::error::Error + 'static))
+            .or_else(|| match &self.kind {
+                ErrorKind::Fs(e) => e.source(),
+                _ => None,
+            })
+    }
+}
@@ -1,29 +1,193 @@
-// use std::fmt::{Display, Formatter};
-use derive_more::{Display, Error, From};
-use crate::error::Error as BaseError;
+use std::path::{Path, PathBuf};
+use derive_more::{Display, Error};
 use crate::error::Result;
 
+/// What to do when a directory entry itself can't be read (e.g. a
+/// permission error or a non-UTF-8 name), as opposed to the listing's
+/// target path being missing or empty.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+// `Warn` and `Abort` are part of the public `ListOptions` API; the bin
+// target only ever constructs the default (`Skip`) today.
+#[allow(dead_code)]
+pub enum OnEntryError {
+    /// Silently drop the offending entry, as `list_files` always has.
+    #[default]
+    Skip,
+    /// Drop the entry but log a warning to stderr.
+    Warn,
+    /// Abort the whole listing with the first failing entry's error.
+    Abort,
+}
+
+/// A predicate deciding whether a candidate path belongs in the listing.
+pub type PathFilter = Box<dyn Fn(&Path) -> bool>;
+
+/// Configuration for [`list_files_with_options`].
+///
+/// `filter` runs against every candidate path (file or, when `include_dirs`
+/// is set, directory) before it is added to the result.
+#[derive(Default)]
+pub struct ListOptions {
+    pub recursive: bool,
+    pub include_dirs: bool,
+    pub filter: Option<PathFilter>,
+    pub on_entry_error: OnEntryError,
+}
+
+/// Thin wrapper over [`list_files_with_options`] with the default options
+/// (non-recursive, files only), kept for backward compatibility.
 pub fn list_files(path: &str) -> Result<Vec<String>> {
-    let files: Vec<String> = std::fs::read_dir(path)?
-        .filter_map(|entry| entry.ok())
-        .filter(|entry| entry.file_type().map(|t| t.is_file()).unwrap_or(false))
-        .filter_map(|entry| entry.file_name().into_string().ok())
-        .collect()
-        ;
+    let files = list_files_with_options(path, &ListOptions::default())?;
+    Ok(files
+        .into_iter()
+        .filter_map(|p| p.file_name().and_then(|n| n.to_str()).map(String::from))
+        .collect())
+}
+
+pub fn list_files_with_options(path: &str, options: &ListOptions) -> Result<Vec<PathBuf>> {
+    let dir = Path::new(path);
+    if !dir.exists() {
+        return Err(Error::PathNotFound(path.to_string()).into());
+    }
+    if !dir.is_dir() {
+        return Err(Error::NotADirectory(path.to_string()).into());
+    }
+
+    let mut files = Vec::new();
+    walk(dir, options, &mut files)?;
+
     if files.is_empty() {
-        return Err(BaseError::Fs(Error::SillyOneCantListEmptyFolder));
+        return Err(Error::EmptyDirectory(path.to_string()).into());
     }
     Ok(files)
 }
 
-#[derive(Debug, From, Display, Error)]
+fn walk(dir: &Path, options: &ListOptions, out: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                handle_entry_error(err, options)?;
+                continue;
+            }
+        };
+
+        let path = entry.path();
+        let file_type = match entry.file_type() {
+            Ok(t) => t,
+            Err(err) => {
+                handle_entry_error(err, options)?;
+                continue;
+            }
+        };
+
+        if file_type.is_dir() {
+            if options.include_dirs && matches_filter(&path, options) {
+                out.push(path.clone());
+            }
+            if options.recursive {
+                walk(&path, options, out)?;
+            }
+        } else if file_type.is_file() && matches_filter(&path, options) {
+            out.push(path);
+        }
+        // Symlinks (and other non-file, non-dir entry types) are skipped,
+        // matching the original `list_files`, which only ever returned
+        // `is_file()` entries.
+    }
+    Ok(())
+}
+
+fn matches_filter(path: &Path, options: &ListOptions) -> bool {
+    options.filter.as_ref().map(|f| f(path)).unwrap_or(true)
+}
+
+fn handle_entry_error(err: std::io::Error, options: &ListOptions) -> Result<()> {
+    match options.on_entry_error {
+        OnEntryError::Skip => Ok(()),
+        OnEntryError::Warn => {
+            eprintln!("warning: skipping unreadable directory entry: {err}");
+            Ok(())
+        }
+        OnEntryError::Abort => Err(Error::EntryUnreadable(err).into()),
+    }
+}
+
+#[derive(Debug, Display, Error)]
 pub enum Error {
-    SillyOneCantListEmptyFolder
+    #[display(fmt = "path not found: {_0}")]
+    PathNotFound(#[error(ignore)] String),
+
+    #[display(fmt = "not a directory: {_0}")]
+    NotADirectory(#[error(ignore)] String),
+
+    #[display(fmt = "directory is empty: {_0}")]
+    EmptyDirectory(#[error(ignore)] String),
+
+    #[display(fmt = "could not read directory entry: {_0}")]
+    EntryUnreadable(#[error(source)] std::io::Error),
 }
 
-// impl Display for Error {
-//     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-//         write!(f, "{self:?}")
-//     }
-// }
-// impl std::error::Error for Error {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "rust-error-handler-test-{name}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn list_files_reports_path_not_found() {
+        let dir = temp_dir("not-found");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let err = list_files(dir.to_str().unwrap()).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::Fs(Error::PathNotFound(_))));
+    }
+
+    #[test]
+    fn list_files_reports_not_a_directory() {
+        let dir = temp_dir("not-a-directory");
+        let file = dir.join("plain.txt");
+        std::fs::write(&file, b"hi").unwrap();
+
+        let err = list_files(file.to_str().unwrap()).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::Fs(Error::NotADirectory(_))));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn list_files_reports_empty_directory() {
+        let dir = temp_dir("empty");
+
+        let err = list_files(dir.to_str().unwrap()).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::Fs(Error::EmptyDirectory(_))));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn on_entry_error_abort_surfaces_the_io_error_via_source_chain() {
+        let options = ListOptions {
+            on_entry_error: OnEntryError::Abort,
+            ..ListOptions::default()
+        };
+        let io_err = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+
+        let err: crate::error::Error = handle_entry_error(io_err, &options).unwrap_err();
+
+        assert!(matches!(err.kind(), ErrorKind::Fs(Error::EntryUnreadable(_))));
+        assert_eq!(
+            err.io_error_source().map(|e| e.kind()),
+            Some(std::io::ErrorKind::PermissionDenied)
+        );
+    }
+}
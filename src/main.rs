@@ -6,7 +6,14 @@ pub use error::{Error, Result};
 
 use crate::fs::list_files;
 
-fn main() -> Result<()>{
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("error: {err}");
+        std::process::exit(err.exit_code());
+    }
+}
+
+fn run() -> Result<()> {
     let files = list_files(".")?;
     println!("{files:#?}");
     Ok(())